@@ -0,0 +1,395 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    ensure_eq, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw_storage_plus::Item;
+
+use cw20::Cw20ExecuteMsg;
+use palomadex::asset::{AssetInfo, AssetInfoValidated};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{Config, CONFIG, PENDING_PAYOUT, ROUTES};
+
+/// If `Some(bridge)`, the conversion currently in flight in `reply` just finished its first
+/// hop (fee -> bridge) and still needs a second swap (bridge -> PALOMA). `None` means the
+/// swap in flight is itself the last hop for its token.
+const CURRENT_CONVERSION: Item<Option<AssetInfoValidated>> = Item::new("current_conversion");
+/// Remaining `(fee_asset, bridge_asset)` conversions still queued after the one in flight
+const PENDING_CONVERSIONS: Item<Vec<(AssetInfoValidated, Option<AssetInfoValidated>)>> =
+    Item::new("pending_conversions");
+/// The contract's PALOMA balance right before a `ConvertFees` call started swapping, so the
+/// amount actually produced by the swaps (and nothing else already sitting in the wallet) can
+/// be credited to `PENDING_PAYOUT` once every hop has landed.
+const PRE_CONVERT_BALANCE: Item<Uint128> = Item::new("pre_convert_balance");
+
+const CONVERT_REPLY_ID: u64 = 1;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender,
+    };
+
+    let config = Config {
+        owner,
+        nominated_trader: deps.api.addr_validate(&msg.nominated_trader)?,
+        beneficiary: deps.api.addr_validate(&msg.beneficiary)?,
+        token_contract: msg.token_contract.validate(deps.api)?,
+        dex_factory_contract: deps.api.addr_validate(&msg.dex_factory_contract)?,
+        max_spread: msg.max_spread,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ConvertFees { tokens } => convert_fees(deps, env, info, tokens),
+        ExecuteMsg::UpdateRoute { asset, bridge } => update_route(deps, info, asset, bridge),
+        ExecuteMsg::DeleteRoute { asset } => delete_route(deps, info, asset),
+        ExecuteMsg::Withdraw {} => withdraw(deps, env, info),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_binary(&CONFIG.load(deps.storage)?)?),
+        QueryMsg::Route { asset } => {
+            let route = ROUTES.may_load(deps.storage, asset.to_string())?;
+            Ok(to_binary(&route)?)
+        }
+        QueryMsg::PendingPayout {} => {
+            let pending = PENDING_PAYOUT.may_load(deps.storage)?.unwrap_or_default();
+            Ok(to_binary(&pending)?)
+        }
+    }
+}
+
+/// Swap the contract's balance of each of `tokens` into PALOMA, accruing it in `PENDING_PAYOUT` for
+/// the beneficiary to claim later via `Withdraw`. Tokens with a bridge token registered in
+/// `ROUTES` are routed fee -> bridge -> PALOMA, everything else is swapped directly to PALOMA.
+pub fn convert_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tokens: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_eq!(
+        info.sender,
+        config.nominated_trader,
+        ContractError::Unauthorized
+    );
+
+    let mut conversions = vec![];
+    let mut accrued = Uint128::zero();
+
+    for token in tokens {
+        let token = token.validate(deps.api)?;
+
+        if token == config.token_contract {
+            // already PALOMA - nothing to swap, just accrue the portion not already
+            // accounted for in PENDING_PAYOUT (the rest is owed to a past accrual)
+            let balance = asset_balance(deps.as_ref(), &env, &token)?;
+            let pending = PENDING_PAYOUT.may_load(deps.storage)?.unwrap_or_default();
+            accrued += balance.saturating_sub(pending);
+            continue;
+        }
+
+        let balance = asset_balance(deps.as_ref(), &env, &token)?;
+        if balance.is_zero() {
+            continue;
+        }
+
+        let bridge = ROUTES
+            .may_load(deps.storage, token.to_string())?
+            .map(|bridge| bridge.validate(deps.api))
+            .transpose()?;
+        conversions.push((token, bridge));
+    }
+
+    if !accrued.is_zero() {
+        accrue_payout(deps.storage, accrued)?;
+    }
+
+    let pre_convert_balance = asset_balance(deps.as_ref(), &env, &config.token_contract)?;
+    PRE_CONVERT_BALANCE.save(deps.storage, &pre_convert_balance)?;
+
+    let res = Response::new().add_attribute("action", "convert_fees");
+
+    match start_conversions(deps, &env, &config, conversions)? {
+        Some(sub_msg) => Ok(res.add_submessage(sub_msg)),
+        None => {
+            PRE_CONVERT_BALANCE.remove(deps.storage);
+            Ok(res)
+        }
+    }
+}
+
+/// Kicks off the first hop of `conversions`, saving the rest to be continued in `reply` once
+/// it lands. Returns `None` if there is nothing to do.
+fn start_conversions(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    mut conversions: Vec<(AssetInfoValidated, Option<AssetInfoValidated>)>,
+) -> Result<Option<SubMsg>, ContractError> {
+    if conversions.is_empty() {
+        return Ok(None);
+    }
+
+    let (fee_asset, bridge) = conversions.remove(0);
+    PENDING_CONVERSIONS.save(deps.storage, &conversions)?;
+    CURRENT_CONVERSION.save(deps.storage, &bridge)?;
+
+    // first hop goes to the bridge token if one is registered, otherwise straight to PALOMA
+    let ask_asset = bridge.as_ref().unwrap_or(&config.token_contract);
+    let balance = asset_balance(deps.as_ref(), env, &fee_asset)?;
+    let swap = swap_msg(deps.as_ref(), config, &fee_asset, ask_asset, balance, None)?;
+    Ok(Some(SubMsg::reply_on_success(swap, CONVERT_REPLY_ID)))
+}
+
+fn accrue_payout(storage: &mut dyn cosmwasm_std::Storage, amount: Uint128) -> StdResult<()> {
+    let pending = PENDING_PAYOUT.may_load(storage)?.unwrap_or_default();
+    PENDING_PAYOUT.save(storage, &(pending + amount))
+}
+
+pub fn update_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+    bridge: AssetInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_eq!(info.sender, config.owner, ContractError::Unauthorized);
+
+    let validated_asset = asset.validate(deps.api)?;
+    let validated_bridge = bridge.validate(deps.api)?;
+    assert_pair_exists(deps.as_ref(), &config, &validated_asset, &validated_bridge)?;
+    assert_pair_exists(
+        deps.as_ref(),
+        &config,
+        &validated_bridge,
+        &config.token_contract,
+    )?;
+
+    ROUTES.save(deps.storage, asset.to_string(), &bridge)?;
+    Ok(Response::new()
+        .add_attribute("action", "update_route")
+        .add_attribute("asset", asset.to_string())
+        .add_attribute("bridge", bridge.to_string()))
+}
+
+pub fn delete_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_eq!(info.sender, config.owner, ContractError::Unauthorized);
+
+    ROUTES.remove(deps.storage, asset.to_string());
+    Ok(Response::new()
+        .add_attribute("action", "delete_route")
+        .add_attribute("asset", asset.to_string()))
+}
+
+/// Pay out the full amount accrued in `PENDING_PAYOUT` to `beneficiary`, resetting it to zero.
+pub fn withdraw(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure_eq!(info.sender, config.beneficiary, ContractError::Unauthorized);
+
+    let pending = PENDING_PAYOUT.may_load(deps.storage)?.unwrap_or_default();
+    PENDING_PAYOUT.save(deps.storage, &Uint128::zero())?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "withdraw")
+        .add_attribute("amount", pending.to_string());
+
+    if !pending.is_zero() {
+        res = res.add_message(transfer_msg(&config.token_contract, pending, &info.sender)?);
+    }
+    Ok(res)
+}
+
+fn assert_pair_exists(
+    deps: Deps,
+    config: &Config,
+    a: &AssetInfoValidated,
+    b: &AssetInfoValidated,
+) -> Result<(), ContractError> {
+    find_pair_addr(deps, config, &a.into(), &b.into())?;
+    Ok(())
+}
+
+fn find_pair_addr(
+    deps: Deps,
+    config: &Config,
+    a: &AssetInfo,
+    b: &AssetInfo,
+) -> Result<String, ContractError> {
+    let pair: palomadex::factory::PairInfo = deps
+        .querier
+        .query_wasm_smart(
+            &config.dex_factory_contract,
+            &palomadex::factory::QueryMsg::Pair {
+                asset_infos: vec![a.clone(), b.clone()],
+            },
+        )
+        .map_err(|_| ContractError::NoPairFound(a.to_string(), b.to_string()))?;
+    Ok(pair.contract_addr.into_string())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != CONVERT_REPLY_ID {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "unknown reply id: {}",
+            msg.id
+        ))));
+    }
+    if msg.result.is_err() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "fee conversion swap failed",
+        )));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut res = Response::new().add_attribute("action", "convert_hop");
+
+    if let Some(bridge_asset) = CURRENT_CONVERSION.load(deps.storage)? {
+        // first hop (fee -> bridge) just landed, still need bridge -> PALOMA
+        let balance = asset_balance(deps.as_ref(), &env, &bridge_asset)?;
+        if !balance.is_zero() {
+            CURRENT_CONVERSION.save(deps.storage, &None)?;
+            let swap = swap_msg(
+                deps.as_ref(),
+                &config,
+                &bridge_asset,
+                &config.token_contract,
+                balance,
+                None,
+            )?;
+            return Ok(res.add_submessage(SubMsg::reply_on_success(swap, CONVERT_REPLY_ID)));
+        }
+    }
+
+    let pending = PENDING_CONVERSIONS.load(deps.storage)?;
+    match start_conversions(deps.branch(), &env, &config, pending)? {
+        Some(sub_msg) => Ok(res.add_submessage(sub_msg)),
+        None => {
+            // the whole conversion queue is done - credit what the swaps actually produced
+            let pre_convert_balance = PRE_CONVERT_BALANCE.load(deps.storage)?;
+            let balance = asset_balance(deps.as_ref(), &env, &config.token_contract)?;
+            let produced = balance.saturating_sub(pre_convert_balance);
+            accrue_payout(deps.storage, produced)?;
+            PRE_CONVERT_BALANCE.remove(deps.storage);
+            res = res.add_attribute("accrued", produced.to_string());
+            Ok(res)
+        }
+    }
+}
+
+fn asset_balance(deps: Deps, env: &Env, asset: &AssetInfoValidated) -> StdResult<Uint128> {
+    match asset {
+        AssetInfoValidated::Token(addr) => {
+            cw20::Cw20Contract(addr.clone()).balance(&deps.querier, &env.contract.address)
+        }
+        AssetInfoValidated::Native(denom) => Ok(deps
+            .querier
+            .query_balance(&env.contract.address, denom)?
+            .amount),
+    }
+}
+
+fn transfer_msg(
+    asset: &AssetInfoValidated,
+    amount: Uint128,
+    to: &Addr,
+) -> Result<CosmosMsg, ContractError> {
+    let msg = match asset {
+        AssetInfoValidated::Token(addr) => WasmMsg::Execute {
+            contract_addr: addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: to.to_string(),
+                amount,
+            })?,
+        }
+        .into(),
+        AssetInfoValidated::Native(denom) => BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        }
+        .into(),
+    };
+    Ok(msg)
+}
+
+/// Builds a swap message for `amount` of `offer_asset` into `ask_asset`, sending the proceeds to
+/// `to` (the contract itself if `None`), enforcing the configured `max_spread`.
+fn swap_msg(
+    deps: Deps,
+    config: &Config,
+    offer_asset: &AssetInfoValidated,
+    ask_asset: &AssetInfoValidated,
+    amount: Uint128,
+    to: Option<Addr>,
+) -> Result<CosmosMsg, ContractError> {
+    let offer_asset_info: AssetInfo = offer_asset.into();
+    let ask_asset_info: AssetInfo = ask_asset.into();
+    let pair_addr = find_pair_addr(deps, config, &offer_asset_info, &ask_asset_info)?;
+
+    let msg = match offer_asset {
+        AssetInfoValidated::Native(denom) => WasmMsg::Execute {
+            contract_addr: pair_addr,
+            funds: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+            msg: to_binary(&palomadex::pair::ExecuteMsg::Swap {
+                offer_asset: palomadex::asset::Asset {
+                    info: offer_asset_info,
+                    amount,
+                },
+                belief_price: None,
+                max_spread: Some(config.max_spread),
+                to: to.map(|a| a.to_string()),
+            })?,
+        },
+        AssetInfoValidated::Token(addr) => WasmMsg::Execute {
+            contract_addr: addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pair_addr,
+                amount,
+                msg: to_binary(&palomadex::pair::Cw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: Some(config.max_spread),
+                    to: to.map(|a| a.to_string()),
+                })?,
+            })?,
+        },
+    };
+    Ok(msg.into())
+}
@@ -0,0 +1,53 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
+use palomadex::asset::AssetInfo;
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address that's allowed to change contract parameters, defaults to the sender
+    pub owner: Option<String>,
+    /// Address that's allowed to perform swaps and convert fee tokens to Paloma as needed
+    pub nominated_trader: String,
+    /// Address specified to receive any payouts, usually distinct from the nominated_trader address
+    pub beneficiary: String,
+    /// The Grain token contract address
+    pub token_contract: AssetInfo,
+    /// The Palomadex factory contract address
+    pub dex_factory_contract: String,
+    /// The maximum spread used when swapping fee tokens to PALOMA
+    pub max_spread: Decimal,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Swap each of `tokens` held by the contract into PALOMA (routing through the bridge
+    /// token registered in `ROUTES`, if any) and accrue the proceeds for `beneficiary` to
+    /// claim with `Withdraw`. Only callable by `nominated_trader`.
+    ConvertFees { tokens: Vec<AssetInfo> },
+    /// Register (or replace) the bridge token used to swap `asset` to PALOMA.
+    /// The pair `asset`/`bridge` and `bridge`/PALOMA must both exist on `dex_factory_contract`.
+    /// Only callable by `owner`.
+    UpdateRoute { asset: AssetInfo, bridge: AssetInfo },
+    /// Remove the bridge token registered for `asset`, falling back to a direct swap to PALOMA.
+    /// Only callable by `owner`.
+    DeleteRoute { asset: AssetInfo },
+    /// Pay out the full amount of PALOMA accrued in `PENDING_PAYOUT` to `beneficiary`.
+    /// Only callable by `beneficiary`.
+    Withdraw {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the current contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Returns the bridge token registered for `asset`, if any
+    #[returns(Option<AssetInfo>)]
+    Route { asset: AssetInfo },
+    /// Returns the amount of PALOMA accrued and awaiting a `Withdraw` call
+    #[returns(Uint128)]
+    PendingPayout {},
+}
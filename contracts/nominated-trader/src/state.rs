@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw_storage_plus::{Item, Map};
 use palomadex::asset::{AssetInfo, AssetInfoValidated};
 
@@ -24,3 +24,7 @@ pub const CONFIG: Item<Config> = Item::new("config");
 
 /// Stores bridge tokens used to swap fee tokens to PALOMA
 pub const ROUTES: Map<String, AssetInfo> = Map::new("routes");
+
+/// PALOMA accrued from converted fees that hasn't been claimed by `beneficiary` yet.
+/// Unset is equivalent to zero.
+pub const PENDING_PAYOUT: Item<Uint128> = Item::new("pending_payout");
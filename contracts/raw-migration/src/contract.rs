@@ -1,16 +1,16 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, ensure_eq, to_binary, Addr, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Order,
-    Reply, Response, StdResult, SubMsg, Uint128, WasmMsg,
+    coin, ensure_eq, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Reply, Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
 
 use cw20::Cw20ExecuteMsg;
-use palomadex::asset::{Asset, AssetInfo};
+use palomadex::asset::{Asset, AssetInfo, AssetInfoValidated};
 use wasmswap::msg::InfoResponse;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, MigrationPreviewResponse, QueryMsg, StakerAllocation};
 use crate::state::{MigrateStakersConfig, DESTINATION, EXCHANGE_CONFIG, MIGRATION};
 
 // this is the contract we are migrating from
@@ -27,18 +27,57 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::MigrationFinished {} => {
             let no_stakers = stake_cw20::state::STAKED_BALANCES
-                .keys(_deps.storage, None, None, Order::Ascending)
+                .keys(deps.storage, None, None, Order::Ascending)
                 .next()
                 .is_none();
             Ok(to_binary(&no_stakers)?)
         }
+        QueryMsg::MigrationPreview { start_after, limit } => {
+            Ok(to_binary(&migration_preview(deps, start_after, limit)?)?)
+        }
     }
 }
 
+fn migration_preview(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<MigrationPreviewResponse, ContractError> {
+    let start_after = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let stakers = find_stakers(deps, start_after.as_ref(), limit)?;
+
+    let config = MIGRATION.load(deps.storage)?.migrate_stakers_config;
+    let (allocations, batch_total_lp) = match config {
+        Some(config) if !config.total_lp_tokens.is_zero() && !config.total_staked.is_zero() => {
+            apportion_lp(stakers, config.total_lp_tokens, config.total_staked)?
+        }
+        // `MigrateTokens` hasn't completed yet (or nobody ever staked), so there is nothing to project
+        _ => (
+            stakers
+                .into_iter()
+                .map(|(addr, stake)| (addr, stake, Uint128::zero()))
+                .collect(),
+            Uint128::zero(),
+        ),
+    };
+
+    Ok(MigrationPreviewResponse {
+        stakers: allocations
+            .into_iter()
+            .map(|(addr, stake, projected_lp)| StakerAllocation {
+                staker: addr.into_string(),
+                stake,
+                projected_lp,
+            })
+            .collect(),
+        batch_total_lp,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -149,46 +188,103 @@ pub fn migrate_stakers(
 
     let config = migration
         .migrate_stakers_config
+        .as_ref()
         .ok_or(ContractError::TokensNotMigrated)?;
 
     // calculate next `limit` stakers and their shares
-    let stakers = find_stakers(deps.as_ref(), limit)?;
+    let stakers = find_stakers(deps.as_ref(), None, limit)?;
+
+    // once there is nobody left to migrate, sweep whatever LP dust is left to the beneficiary
+    if stakers.is_empty() {
+        return sweep_leftover_lp(deps.as_ref(), env, &migration.beneficiary, config);
+    }
 
     // remove the processed stakers from the state
     remove_stakers(deps.branch(), &env, stakers.iter().map(|(addr, _)| addr))?;
 
-    let staker_lps: Vec<_> = stakers
+    // Hamilton (largest-remainder) apportionment so nobody in the batch is shorted by
+    // integer division - see `apportion_lp` for the details
+    let (entries, batch_lp) =
+        apportion_lp(stakers, config.total_lp_tokens, config.total_staked)?;
+
+    let staker_lps: Vec<_> = entries
         .into_iter()
-        .map(|(addr, stake)| {
-            (
-                addr.to_string(),
-                stake * config.total_lp_tokens / config.total_staked,
-            )
-        })
+        .map(|(addr, _stake, amount)| (addr.to_string(), amount))
         .filter(|(_, x)| !x.is_zero())
         .collect();
 
-    // the amount of LP tokens we are migrating in this message
-    let batch_lp: Uint128 = staker_lps.iter().map(|(_, x)| x).sum();
+    // bond it all on behalf of the stakers, either via a cw20 Send or a native-funded call,
+    // depending on how this PALOMA DEX pool issues its LP shares
+    let stake_msg = match &config.lp_token {
+        AssetInfoValidated::Token(lp_token) => {
+            let bond_msg = palomadex::stake::ReceiveMsg::MassDelegate {
+                unbonding_period: migration.unbonding_period,
+                delegate_to: staker_lps,
+            };
+            WasmMsg::Execute {
+                contract_addr: lp_token.to_string(),
+                funds: vec![],
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: config.staking_addr.to_string(),
+                    amount: batch_lp,
+                    msg: to_binary(&bond_msg)?,
+                })?,
+            }
+        }
+        AssetInfoValidated::Native(denom) => WasmMsg::Execute {
+            contract_addr: config.staking_addr.to_string(),
+            funds: vec![coin(batch_lp.u128(), denom)],
+            msg: to_binary(&palomadex::stake::ExecuteMsg::MassDelegate {
+                unbonding_period: migration.unbonding_period,
+                delegate_to: staker_lps,
+            })?,
+        },
+    };
+
+    Ok(Response::new().add_message(stake_msg))
+}
 
-    // bonding has full info on who receives the delegation
-    let bond_msg = palomadex::stake::ReceiveMsg::MassDelegate {
-        unbonding_period: migration.unbonding_period,
-        delegate_to: staker_lps,
+/// Called once `find_stakers` has nothing left to migrate. Per-batch apportionment in
+/// `migrate_stakers` can still leave a few units of LP unassigned across batch boundaries,
+/// so sweep whatever is actually left in the contract's LP balance to `beneficiary` to
+/// guarantee a zero LP balance at the end.
+fn sweep_leftover_lp(
+    deps: Deps,
+    env: Env,
+    beneficiary: &Addr,
+    config: &MigrateStakersConfig,
+) -> Result<Response, ContractError> {
+    let leftover = match &config.lp_token {
+        AssetInfoValidated::Token(addr) => {
+            cw20::Cw20Contract(addr.clone()).balance(&deps.querier, env.contract.address)?
+        }
+        AssetInfoValidated::Native(denom) => {
+            deps.querier.query_balance(env.contract.address, denom)?.amount
+        }
     };
 
-    // stake it all
-    let stake_msg = WasmMsg::Execute {
-        contract_addr: config.lp_token.to_string(),
-        funds: vec![],
-        msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
-            contract: config.staking_addr.into_string(),
-            amount: batch_lp,
-            msg: to_binary(&bond_msg)?,
-        })?,
+    if leftover.is_zero() {
+        return Ok(Response::new());
+    }
+
+    let sweep_msg: cosmwasm_std::CosmosMsg = match &config.lp_token {
+        AssetInfoValidated::Token(addr) => WasmMsg::Execute {
+            contract_addr: addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: beneficiary.to_string(),
+                amount: leftover,
+            })?,
+        }
+        .into(),
+        AssetInfoValidated::Native(denom) => cosmwasm_std::BankMsg::Send {
+            to_address: beneficiary.to_string(),
+            amount: vec![coin(leftover.u128(), denom)],
+        }
+        .into(),
     };
 
-    Ok(Response::new().add_message(stake_msg))
+    Ok(Response::new().add_message(sweep_msg))
 }
 
 const REPLY_ONE: u64 = 111;
@@ -225,13 +321,17 @@ pub fn reply_one(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
         funds,
         new_assets,
     } = prepare_denom_deposits(deps.as_ref(), &destination, &assets)?;
+
+    // the withdrawal and the deposit are separate messages, so the destination pool's ratio
+    // may have moved in between - bail out rather than deposit at an unfavorable rate
+    check_deposit_ratio(deps.as_ref(), &destination, &new_assets, migration.max_slippage)?;
+
     let deposit = WasmMsg::Execute {
         contract_addr: destination.into_string(),
         funds,
         msg: to_binary(&palomadex::pair::ExecuteMsg::ProvideLiquidity {
             assets: new_assets,
-            // TODO: set some value here?
-            slippage_tolerance: None,
+            slippage_tolerance: Some(migration.max_slippage),
             receiver: None,
         })?,
     };
@@ -243,6 +343,48 @@ pub fn reply_one(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     Ok(res)
 }
 
+/// Compares the ratio of `new_assets` (what we are about to deposit) against the destination
+/// pool's current reserves, aborting if it deviates from `max_slippage`. An unseeded pool
+/// (either reserve is zero) sets its own initial ratio, so it is not checked.
+fn check_deposit_ratio(
+    deps: Deps,
+    destination: &Addr,
+    new_assets: &[Asset],
+    max_slippage: Decimal,
+) -> Result<(), ContractError> {
+    let pool: palomadex::pair::PoolResponse = deps
+        .querier
+        .query_wasm_smart(destination, &palomadex::pair::QueryMsg::Pool {})?;
+
+    let reserve0 = pool.assets.iter().find(|a| a.info == new_assets[0].info);
+    let reserve1 = pool.assets.iter().find(|a| a.info == new_assets[1].info);
+    if let (Some(reserve0), Some(reserve1)) = (reserve0, reserve1) {
+        if !reserve0.amount.is_zero() && !reserve1.amount.is_zero() {
+            // a zero deposit amount against a nonzero reserve is an infinite deviation -
+            // reject it the same as any other slippage violation instead of panicking
+            let deposit_ratio =
+                Decimal::checked_from_ratio(new_assets[0].amount, new_assets[1].amount)
+                    .map_err(|_| ContractError::MaxSlippageExceeded { max_slippage })?;
+            let pool_ratio = Decimal::from_ratio(reserve0.amount, reserve1.amount);
+            let deviation = if deposit_ratio > pool_ratio {
+                deposit_ratio - pool_ratio
+            } else {
+                pool_ratio - deposit_ratio
+            };
+            // compare via multiplication rather than `deviation / pool_ratio` so a pool_ratio
+            // that rounds to zero can't cause a divide-by-zero panic
+            let allowed_deviation = pool_ratio
+                .checked_mul(max_slippage)
+                .map_err(|_| ContractError::MaxSlippageExceeded { max_slippage })?;
+            if deviation > allowed_deviation {
+                return Err(ContractError::MaxSlippageExceeded { max_slippage });
+            }
+        }
+    }
+
+    Ok(())
+}
+
 struct DenomDeposits {
     allowances: Vec<WasmMsg>,
     funds: Vec<Coin>,
@@ -371,9 +513,16 @@ pub fn reply_two(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let mut migration = MIGRATION.load(deps.storage)?;
     let config = migration.migrate_stakers_config.as_mut().unwrap();
 
-    // how many LP do we have total
-    let lp_token = cw20::Cw20Contract(config.lp_token.clone());
-    let total_lp_tokens = lp_token.balance(&deps.querier, env.contract.address)?;
+    // how many LP do we have total - LP may be a legacy cw20 or a native token-factory denom
+    let total_lp_tokens = match &config.lp_token {
+        AssetInfoValidated::Token(addr) => {
+            cw20::Cw20Contract(addr.clone()).balance(&deps.querier, env.contract.address)?
+        }
+        AssetInfoValidated::Native(denom) => deps
+            .querier
+            .query_balance(env.contract.address, denom)?
+            .amount,
+    };
 
     // store this for `migrate_stakers` to use
     config.total_lp_tokens = total_lp_tokens;
@@ -383,9 +532,14 @@ pub fn reply_two(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
 }
 
 // query logic taken from https://github.com/cosmorama/wyndex-priv/pull/109
-fn find_stakers(deps: Deps, limit: impl Into<Option<u32>>) -> StdResult<Vec<(Addr, Uint128)>> {
+fn find_stakers(
+    deps: Deps,
+    start_after: Option<&Addr>,
+    limit: impl Into<Option<u32>>,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    let start = start_after.map(|addr| cw_storage_plus::Bound::exclusive(addr.clone()));
     let balances = stake_cw20::state::STAKED_BALANCES
-        .range(deps.storage, None, None, Order::Ascending)
+        .range(deps.storage, start, None, Order::Ascending)
         .map(|stake| {
             let (addr, amount) = stake?;
 
@@ -401,6 +555,50 @@ fn find_stakers(deps: Deps, limit: impl Into<Option<u32>>) -> StdResult<Vec<(Add
     }
 }
 
+/// Applies Hamilton (largest-remainder) apportionment, splitting `total_lp_tokens` among
+/// `stakers` in proportion to their stake out of `total_staked`. Shared by `migrate_stakers`
+/// and the `MigrationPreview` query so the preview always matches what an actual call would do.
+fn apportion_lp(
+    stakers: Vec<(Addr, Uint128)>,
+    total_lp_tokens: Uint128,
+    total_staked: Uint128,
+) -> Result<(Vec<(Addr, Uint128, Uint128)>, Uint128), ContractError> {
+    if total_staked.is_zero() {
+        return Err(ContractError::NothingStaked);
+    }
+
+    let batch_total_stake: Uint128 = stakers.iter().map(|(_, stake)| *stake).sum();
+    let batch_lp = batch_total_stake * total_lp_tokens / total_staked;
+
+    // (address, stake, floor share, remainder) - sorted by largest remainder, ties by address
+    let mut entries: Vec<(Addr, Uint128, Uint128, Uint128)> = stakers
+        .into_iter()
+        .map(|(addr, stake)| {
+            let numerator = stake * total_lp_tokens;
+            let floor = numerator / total_staked;
+            let remainder = numerator % total_staked;
+            (addr, stake, floor, remainder)
+        })
+        .collect();
+    entries.sort_by(|a, b| b.3.cmp(&a.3).then_with(|| a.0.cmp(&b.0)));
+
+    let floor_sum: Uint128 = entries.iter().map(|(_, _, floor, _)| *floor).sum();
+    let mut leftover = batch_lp - floor_sum;
+    for entry in entries.iter_mut() {
+        if leftover.is_zero() {
+            break;
+        }
+        entry.2 += Uint128::one();
+        leftover -= Uint128::one();
+    }
+
+    let allocations = entries
+        .into_iter()
+        .map(|(addr, stake, amount, _)| (addr, stake, amount))
+        .collect();
+    Ok((allocations, batch_lp))
+}
+
 fn remove_stakers<'a>(
     deps: DepsMut,
     env: &Env,
@@ -0,0 +1,32 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("This contract does not support direct instantiation, it is deployed pre-configured")]
+    NotImplemented,
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("{0} is not a valid migration destination")]
+    InvalidDestination(String),
+
+    #[error("Tokens have not been migrated yet, call MigrateTokens first")]
+    TokensNotMigrated,
+
+    #[error("Received an error in reply")]
+    ErrorReply,
+
+    #[error("Deposit ratio would exceed the configured max slippage of {max_slippage}")]
+    MaxSlippageExceeded { max_slippage: cosmwasm_std::Decimal },
+
+    #[error("Unknown reply id: {0}")]
+    UnknownReply(u64),
+
+    #[error("Cannot apportion LP: total staked amount is zero")]
+    NothingStaked,
+}
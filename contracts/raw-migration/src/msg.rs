@@ -0,0 +1,44 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Withdraw all LP tokens held by the staking contract from the JunoSwap pool and
+    /// deposit them into `palomadex_pool`. Only callable by the configured `migrator`.
+    MigrateTokens { palomadex_pool: String },
+    /// Bond the migrated PALOMA DEX LP tokens on behalf of up to `limit` JunoSwap stakers.
+    /// Call repeatedly until `MigrationFinished` returns `true`; one more call after that
+    /// sends any LP left in the contract to `beneficiary`.
+    MigrateStakers { limit: u32 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns `true` once every JunoSwap staker has been migrated
+    #[returns(bool)]
+    MigrationFinished {},
+    /// Previews the LP allocation a `MigrateStakers { limit }` call would hand out for the
+    /// next batch of stakers, using the same proportional math. Lets integrators audit who
+    /// will receive what, and reconcile totals off-chain, before and between calls.
+    #[returns(MigrationPreviewResponse)]
+    MigrationPreview {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct StakerAllocation {
+    pub staker: String,
+    pub stake: Uint128,
+    pub projected_lp: Uint128,
+}
+
+#[cw_serde]
+pub struct MigrationPreviewResponse {
+    pub stakers: Vec<StakerAllocation>,
+    /// Total LP this batch would be allocated - zero if `total_lp_tokens` isn't known yet
+    /// (i.e. `MigrateTokens` hasn't completed)
+    pub batch_total_lp: Uint128,
+}
@@ -0,0 +1,58 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::Item;
+use palomadex::asset::AssetInfoValidated;
+
+/// Config for the second phase of the migration - bonding the freshly minted
+/// PALOMA DEX LP tokens on behalf of the original JunoSwap LP stakers.
+#[cw_serde]
+pub struct MigrateStakersConfig {
+    /// The PALOMA DEX pool's LP token - either a legacy cw20 or a native token-factory denom
+    pub lp_token: AssetInfoValidated,
+    /// The PALOMA DEX staking contract for this pool
+    pub staking_addr: Addr,
+    /// Total LP tokens received for the withdrawn JunoSwap liquidity, filled in by `reply_two`
+    pub total_lp_tokens: Uint128,
+    /// Total amount staked (+ pending claims) in the JunoSwap staking contract, snapshotted in `migrate_tokens`
+    pub total_staked: Uint128,
+}
+
+#[cw_serde]
+pub struct Migration {
+    /// Address allowed to trigger the migration steps
+    pub migrator: Addr,
+    /// The JunoSwap pool we are migrating liquidity out of
+    pub junoswap_pool: Addr,
+    /// The PALOMA DEX factory, used to validate the destination pool
+    pub factory: Addr,
+    /// If set, the only PALOMA DEX pool `migrate_tokens` is allowed to target
+    pub palomadex_pool: Option<Addr>,
+    /// Receives any LP dust left in the contract once every staker has been migrated
+    pub beneficiary: Addr,
+    /// Unbonding period to use when bonding migrated LP on behalf of stakers
+    pub unbonding_period: u64,
+    /// Maximum allowed deviation between the withdrawn assets' ratio and the destination
+    /// pool's current ratio when depositing liquidity in `reply_one`
+    pub max_slippage: Decimal,
+    /// Filled in by `migrate_tokens` once the destination pool is known
+    pub migrate_stakers_config: Option<MigrateStakersConfig>,
+}
+
+#[cw_serde]
+pub struct ExchangeConfig {
+    /// The old RAW cw20 token, burned on migration
+    pub raw_token: Addr,
+    /// The new GRAIN cw20 token, minted in place of RAW
+    pub grain_token: Addr,
+    /// How many GRAIN to mint per RAW burned
+    pub raw_to_grain_exchange_rate: Decimal,
+}
+
+/// The overall migration state, set up at instantiation and updated as the migration progresses
+pub const MIGRATION: Item<Migration> = Item::new("migration");
+
+/// The PALOMA DEX pool we are depositing the withdrawn JunoSwap liquidity into, saved for the reply handlers
+pub const DESTINATION: Item<Addr> = Item::new("destination");
+
+/// RAW -> GRAIN token swap configuration, used while depositing liquidity
+pub const EXCHANGE_CONFIG: Item<ExchangeConfig> = Item::new("exchange_config");